@@ -1,20 +1,41 @@
 use clap::{Parser, Subcommand};
-use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use std::fs::{File, OpenOptions};
 use std::io::{ErrorKind, Read, Write};
-use std::path::{Path, PathBuf};
-use std::time::SystemTime;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::time::{Duration, SystemTime};
 
-lazy_static! {
-    static ref DATA_FILE_PATH: PathBuf = {
-        let mut data_file_path = directories::ProjectDirs::from("", "kerstop", "git_subscribe")
-            .expect("user home directory should be available")
-            .data_local_dir()
-            .to_owned();
-        data_file_path.set_file_name("data.toml");
-        data_file_path
-    };
+/// Errors surfaced to the user by `main`.
+#[derive(Debug, thiserror::Error)]
+enum Error {
+    #[error("unable to access {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("malformed config in {path}: {source}")]
+    Toml {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+
+    #[error("{0} is not a git repository")]
+    NotAGitRepository(PathBuf),
+
+    #[error("could not determine the user's home directory")]
+    MissingHomeDir,
+}
+
+/// Resolve the path of the `data.toml` database inside the platform data dir.
+fn data_file_path() -> Result<PathBuf, Error> {
+    let mut path = directories::ProjectDirs::from("", "kerstop", "git_subscribe")
+        .ok_or(Error::MissingHomeDir)?
+        .data_local_dir()
+        .to_owned();
+    path.push("data.toml");
+    Ok(path)
 }
 
 #[derive(Parser, Debug)]
@@ -26,19 +47,97 @@ struct Args {
 #[derive(Debug, Subcommand)]
 enum Commands {
     /// list out the tracked repositories
-    List,
+    List {
+        /// only show repositories matching this fuzzy filter
+        filter: Option<String>,
+    },
 
     /// start tracking a repository
     Add { repo: Option<PathBuf> },
 
     /// stop tracking a repository
-    Remove { repo: Option<PathBuf> },
+    Remove {
+        repo: Option<PathBuf>,
+        /// pick the repository to remove from a fuzzy selector
+        #[arg(long)]
+        pick: bool,
+    },
+
+    /// fetch tracked repositories and report new commits
+    Fetch {
+        repo: Option<PathBuf>,
+        /// send a summary email for repos with notification config
+        #[arg(long)]
+        notify: bool,
+    },
+
+    /// continuously monitor tracked repositories for new commits
+    Watch {
+        /// seconds between polling fetches (defaults to 300)
+        #[arg(long)]
+        interval: Option<u64>,
+        /// send a summary email for repos with notification config
+        #[arg(long)]
+        notify: bool,
+    },
+
+    /// manage the commands run when a repository updates
+    Hook {
+        #[command(subcommand)]
+        action: HookAction,
+    },
+
+    /// clone a remote repository and start tracking it
+    Clone {
+        url: String,
+        dest: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum HookAction {
+    /// attach an on-update command to a tracked repository
+    Add {
+        repo: Option<PathBuf>,
+        /// command to run, e.g. `-- make deploy`
+        #[arg(last = true, required = true)]
+        command: Vec<String>,
+    },
+
+    /// list the on-update commands of a tracked repository
+    List { repo: Option<PathBuf> },
+
+    /// detach an on-update command by its index (see `hook list`)
+    Remove {
+        repo: Option<PathBuf>,
+        index: usize,
+    },
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct TrackedRepo {
     path: PathBuf,
     last_fetch: SystemTime,
+    #[serde(default)]
+    notify: Option<NotifyConfig>,
+    /// Commands (program plus args) run whenever this repo gains commits.
+    #[serde(default)]
+    on_update: Vec<Vec<String>>,
+}
+
+/// How to deliver an "upstream changed" email for a tracked repository.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct NotifyConfig {
+    /// Address the summary is sent to.
+    to: String,
+    /// Address the summary is sent from.
+    from: String,
+    /// `host:port` of an SMTP relay. Takes precedence over `sendmail`.
+    #[serde(default)]
+    smtp: Option<String>,
+    /// Sendmail-style command (program plus args) to pipe the message into.
+    #[serde(default)]
+    sendmail: Option<Vec<String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -49,38 +148,47 @@ struct ApplicationData {
 fn main() {
     let args = Args::parse();
 
-    match args.command {
-        Commands::List => command_list(),
+    let result = match args.command {
+        Commands::List { filter } => command_list(filter),
         Commands::Add { repo } => command_add(repo),
-        Commands::Remove { repo } => command_remove(repo),
+        Commands::Remove { repo, pick } => command_remove(repo, pick),
+        Commands::Fetch { repo, notify } => command_fetch(repo, notify),
+        Commands::Watch { interval, notify } => command_watch(interval, notify),
+        Commands::Hook { action } => command_hook(action),
+        Commands::Clone { url, dest } => command_clone(url, dest),
+    };
+
+    if let Err(e) = result {
+        eprintln!("error: {e}");
+        std::process::exit(1);
     }
 }
 
-fn command_list() {
-    let data = load_app_data();
-    println!("{data:?}");
-    println!();
-    for entry in data.tracked_repos {
+fn command_list(filter: Option<String>) -> Result<(), Error> {
+    let data = load_app_data()?;
+    for entry in &data.tracked_repos {
         let path = &entry.path;
+        if let Some(query) = &filter {
+            if fuzzy_score(query, &path.to_string_lossy()).is_none() {
+                continue;
+            }
+        }
         let time = humantime::format_duration(entry.last_fetch.elapsed().unwrap());
         println!("{:<30} | {}", path.to_string_lossy(), time)
     }
+    Ok(())
 }
 
-fn command_add(path: Option<PathBuf>) {
-    let mut data = load_app_data();
+fn command_add(path: Option<PathBuf>) -> Result<(), Error> {
+    let mut data = load_app_data()?;
 
-    let repo_result = match path {
-        Some(p) => git2::Repository::open(p),
-        None => git2::Repository::open(
-            std::env::current_dir().expect("should be able to access current working dir"),
-        ),
+    let resolv_path = match path {
+        Some(p) => p,
+        None => std::env::current_dir().expect("should be able to access current working dir"),
     };
 
-    let repo = match repo_result {
-        Ok(r) => r,
-        Err(e) => panic!("unexpected error: {e}"),
-    };
+    let repo = git2::Repository::open(&resolv_path)
+        .map_err(|_| Error::NotAGitRepository(resolv_path))?;
 
     let mut path = repo.path().to_owned();
 
@@ -91,15 +199,28 @@ fn command_add(path: Option<PathBuf>) {
     let new_entry = TrackedRepo {
         path: path,
         last_fetch: SystemTime::from(std::time::UNIX_EPOCH),
+        notify: None,
+        on_update: Vec::new(),
     };
 
     data.tracked_repos.push(new_entry);
 
-    write_app_data(data);
+    write_app_data(data)
 }
 
-fn command_remove(path: Option<PathBuf>) {
-    let mut data = load_app_data();
+fn command_remove(path: Option<PathBuf>, pick: bool) -> Result<(), Error> {
+    let mut data = load_app_data()?;
+
+    // With no explicit path and `--pick`, let the user fuzzy-select a repo.
+    if path.is_none() && pick {
+        match pick_repo(&data) {
+            Some(i) => {
+                data.tracked_repos.remove(i);
+            }
+            None => println!("nothing selected"),
+        }
+        return write_app_data(data);
+    }
 
     let resolv_path = match path {
         Some(p) => p,
@@ -122,59 +243,812 @@ fn command_remove(path: Option<PathBuf>) {
         ),
     }
 
-    write_app_data(data);
+    write_app_data(data)
+}
+
+fn command_fetch(path: Option<PathBuf>, notify: bool) -> Result<(), Error> {
+    let mut data = load_app_data()?;
+    validate_app_data(&data)?;
+
+    for entry in data.tracked_repos.iter_mut() {
+        if let Some(ref selected) = path {
+            if !same_file::is_same_file(selected, &entry.path).unwrap_or(false) {
+                continue;
+            }
+        }
+        let updates = collect_repo_updates(entry);
+        report_updates(entry, &updates);
+        if !updates.is_empty() {
+            if notify {
+                send_notification(entry, &updates);
+            }
+            run_hooks(entry, &updates);
+        }
+        entry.last_fetch = SystemTime::now();
+    }
+
+    write_app_data(data)
+}
+
+/// Default polling interval for `watch`, in seconds.
+const DEFAULT_WATCH_INTERVAL: u64 = 300;
+
+/// Events that can trigger a re-check of the tracked repositories. Both the
+/// polling loop and the filesystem watcher funnel into a single stream so
+/// future notification backends only have to subscribe to one channel.
+enum WatchEvent {
+    /// The polling interval elapsed; re-check every repo.
+    Tick,
+    /// A watched file inside a repo's `.git` changed.
+    FileUpdated(PathBuf),
+}
+
+fn command_watch(interval: Option<u64>, notify: bool) -> Result<(), Error> {
+    let data = load_app_data()?;
+    validate_app_data(&data)?;
+    let interval = Duration::from_secs(interval.unwrap_or(DEFAULT_WATCH_INTERVAL));
+
+    let (tx, rx) = mpsc::channel::<WatchEvent>();
+
+    // Polling loop: wake up on the configured interval and ask for a re-check.
+    let poll_tx = tx.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(interval);
+        if poll_tx.send(WatchEvent::Tick).is_err() {
+            break;
+        }
+    });
+
+    // Filesystem watcher: forward changes to local branch refs
+    // (`.git/refs/heads`) so commits and pulls made by other tools are noticed
+    // immediately. `FETCH_HEAD` and remote-tracking refs are not watched.
+    let fs_tx = tx.clone();
+    let mut watcher = match notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        if let Ok(event) = res {
+            for path in event.paths {
+                let _ = fs_tx.send(WatchEvent::FileUpdated(path));
+            }
+        }
+    }) {
+        Ok(w) => w,
+        Err(e) => panic!("unable to create filesystem watcher: {e}"),
+    };
+
+    use notify::Watcher;
+    for entry in &data.tracked_repos {
+        // Only watch local branches (`refs/heads`). Our own fetches write
+        // `FETCH_HEAD` and `refs/remotes/**`, so watching those would make
+        // every check re-trigger itself in an unbounded loop.
+        let target = entry.path.join(".git").join("refs").join("heads");
+        if target.exists() {
+            if let Err(e) = watcher.watch(&target, notify::RecursiveMode::Recursive) {
+                println!("unable to watch {}: {e}", target.display());
+            }
+        }
+    }
+
+    println!("watching {} repositories", data.tracked_repos.len());
+
+    // Central handler: every event re-runs the new-commit diffing logic.
+    for event in rx {
+        match event {
+            WatchEvent::Tick => {}
+            WatchEvent::FileUpdated(path) => {
+                println!("change detected at {}", path.display());
+            }
+        }
+        for entry in &data.tracked_repos {
+            let updates = collect_repo_updates(entry);
+            report_updates(entry, &updates);
+            if !updates.is_empty() {
+                if notify {
+                    send_notification(entry, &updates);
+                }
+                run_hooks(entry, &updates);
+            }
+        }
+    }
+
+    Ok(())
 }
 
-fn load_app_data() -> ApplicationData {
-    dbg!(DATA_FILE_PATH.as_path());
-    let file: Option<File> = match OpenOptions::new()
-        .read(true)
-        .write(false)
-        .open(DATA_FILE_PATH.as_path())
+/// A branch that gained commits during a fetch.
+#[derive(Debug, Clone)]
+struct BranchUpdate {
+    branch: String,
+    old_tip: git2::Oid,
+    new_tip: git2::Oid,
+    count: usize,
+}
+
+/// Print a per-branch summary of the commits a repository gained.
+fn report_updates(entry: &TrackedRepo, updates: &[BranchUpdate]) {
+    for update in updates {
+        println!(
+            "{}: {} advanced by {} commit(s)",
+            entry.path.display(),
+            update.branch,
+            update.count
+        );
+    }
+}
+
+/// Perform the fetch for a single tracked repository and return the set of
+/// branches that advanced. Shared by the one-shot `fetch` command and the
+/// long-lived `watch` loop.
+fn collect_repo_updates(entry: &TrackedRepo) -> Vec<BranchUpdate> {
+    let repo = match git2::Repository::open(&entry.path) {
+        Ok(r) => r,
+        Err(e) => {
+            println!("{}: unable to open repository: {e}", entry.path.display());
+            return Vec::new();
+        }
+    };
+
+    // Remember the tips of every upstream branch before fetching so we can
+    // diff against them afterwards.
+    let mut old_tips: Vec<(String, git2::Oid)> = Vec::new();
+    if let Ok(branches) = repo.branches(Some(git2::BranchType::Local)) {
+        for (branch, _) in branches.flatten() {
+            let name = match branch.name() {
+                Ok(Some(n)) => n.to_owned(),
+                _ => continue,
+            };
+            if let Ok(upstream) = branch.upstream() {
+                if let Some(oid) = upstream.get().target() {
+                    old_tips.push((name, oid));
+                }
+            }
+        }
+    }
+
+    let remotes = match repo.remotes() {
+        Ok(r) => r,
+        Err(e) => {
+            println!("{}: unable to list remotes: {e}", entry.path.display());
+            return Vec::new();
+        }
+    };
+
+    for remote_name in remotes.iter().flatten() {
+        let mut remote = match repo.find_remote(remote_name) {
+            Ok(r) => r,
+            Err(e) => {
+                println!("{}: unable to open remote {remote_name}: {e}", entry.path.display());
+                continue;
+            }
+        };
+        let refspecs: Vec<String> = Vec::new();
+        let mut options = git2::FetchOptions::new();
+        options.remote_callbacks(make_remote_callbacks());
+        if let Err(e) = remote.fetch(&refspecs, Some(&mut options), None) {
+            println!("{}: fetch from {remote_name} failed: {e}", entry.path.display());
+        }
+    }
+
+    let mut updates = Vec::new();
+    for (name, old_oid) in &old_tips {
+        let upstream = match repo
+            .find_branch(name, git2::BranchType::Local)
+            .and_then(|b| b.upstream())
+        {
+            Ok(u) => u,
+            Err(_) => continue,
+        };
+        let new_oid = match upstream.get().target() {
+            Some(oid) => oid,
+            None => continue,
+        };
+        if new_oid == *old_oid {
+            continue;
+        }
+        let count = count_new_commits(&repo, *old_oid, new_oid);
+        if count > 0 {
+            updates.push(BranchUpdate {
+                branch: name.clone(),
+                old_tip: *old_oid,
+                new_tip: new_oid,
+                count,
+            });
+        }
+    }
+
+    if updates.is_empty() {
+        println!("{}: up to date", entry.path.display());
+    }
+
+    updates
+}
+
+/// Build the credential callbacks used for every authenticated remote
+/// operation. Authentication is attempted in order: the SSH agent, then each
+/// private key discovered under `~/.ssh` (prompting for a passphrase for
+/// encrypted keys, including `bcrypt-pbkdf` ones), and finally
+/// username/password or token credentials.
+fn make_remote_callbacks() -> git2::RemoteCallbacks<'static> {
+    let keys = discover_ssh_keys();
+    let mut agent_tried = false;
+    // Cursor into `keys`, plus whether we have already tried the current key
+    // without a passphrase (git2 re-invokes this callback on each failure).
+    let mut key_cursor = 0;
+    let mut tried_plain = false;
+    let mut callbacks = git2::RemoteCallbacks::new();
+    callbacks.credentials(move |_url, username_from_url, allowed| {
+        let username = username_from_url.unwrap_or("git");
+
+        if allowed.contains(git2::CredentialType::SSH_KEY) {
+            // First try the agent, then fall back to on-disk keys.
+            if !agent_tried {
+                agent_tried = true;
+                if let Ok(cred) = git2::Cred::ssh_key_from_agent(username) {
+                    return Ok(cred);
+                }
+            }
+
+            while let Some(key) = keys.get(key_cursor) {
+                if !tried_plain {
+                    // Attempt the key without a passphrase first.
+                    tried_plain = true;
+                    return git2::Cred::ssh_key(username, None, key, None);
+                }
+                // The plain attempt failed; prompt for a passphrase only if the
+                // key is actually encrypted, otherwise move on to the next one.
+                if is_encrypted_key(key) {
+                    let passphrase =
+                        ask_secret(&format!("Enter passphrase for {}: ", key.display()));
+                    key_cursor += 1;
+                    tried_plain = false;
+                    return git2::Cred::ssh_key(username, None, key, passphrase.as_deref());
+                }
+                key_cursor += 1;
+                tried_plain = false;
+            }
+        }
+
+        if allowed.contains(git2::CredentialType::USER_PASS_PLAINTEXT) {
+            let user = ask_secret("Username: ").unwrap_or_else(|| username.to_owned());
+            let pass = ask_secret("Password or token: ").unwrap_or_default();
+            return git2::Cred::userpass_plaintext(&user, &pass);
+        }
+
+        if allowed.contains(git2::CredentialType::USERNAME) {
+            return git2::Cred::username(username);
+        }
+
+        Err(git2::Error::from_str("no usable authentication method"))
+    });
+    callbacks
+}
+
+/// Collect the default-named private keys (`id_ed25519`, `id_ecdsa`, `id_rsa`,
+/// `id_dsa`) that exist under `~/.ssh`, in that order. Other key files are not
+/// considered.
+fn discover_ssh_keys() -> Vec<PathBuf> {
+    let home = match std::env::var_os("HOME") {
+        Some(h) => PathBuf::from(h),
+        None => return Vec::new(),
+    };
+    let ssh_dir = home.join(".ssh");
+    ["id_ed25519", "id_ecdsa", "id_rsa", "id_dsa"]
+        .iter()
+        .map(|name| ssh_dir.join(name))
+        .filter(|path| path.exists())
+        .collect()
+}
+
+/// Best-effort check for whether an on-disk private key is passphrase-encrypted
+/// so we only prompt when a passphrase is actually required.
+fn is_encrypted_key(path: &std::path::Path) -> bool {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(_) => return false,
+    };
+
+    // Classic PEM keys advertise encryption directly.
+    if contents.contains("ENCRYPTED") {
+        return true;
+    }
+
+    // OpenSSH-format keys embed the cipher name in the base64 body. An
+    // unencrypted key uses the "none" cipher, which produces a fixed prefix;
+    // anything else (aes + bcrypt-pbkdf, etc.) means it is encrypted.
+    if contents.contains("OPENSSH PRIVATE KEY") {
+        const UNENCRYPTED_PREFIX: &str = "b3BlbnNzaC1rZXktdjEAAAAABG5vbmU";
+        let body: String = contents
+            .lines()
+            .filter(|l| !l.contains("OPENSSH PRIVATE KEY"))
+            .collect();
+        return !body.starts_with(UNENCRYPTED_PREFIX);
+    }
+
+    false
+}
+
+/// Obtain a secret (passphrase or token) using an askpass helper when one is
+/// configured via `GIT_ASKPASS`/`SSH_ASKPASS`, otherwise by prompting on the
+/// controlling terminal.
+fn ask_secret(prompt: &str) -> Option<String> {
+    if let Some(helper) = std::env::var_os("GIT_ASKPASS").or_else(|| std::env::var_os("SSH_ASKPASS"))
     {
+        if let Ok(output) = std::process::Command::new(helper).arg(prompt).output() {
+            let answer = String::from_utf8_lossy(&output.stdout).trim_end().to_owned();
+            if !answer.is_empty() {
+                return Some(answer);
+            }
+        }
+    }
+
+    let mut tty = match OpenOptions::new().read(true).write(true).open("/dev/tty") {
+        Ok(f) => f,
+        Err(_) => return None,
+    };
+    let _ = tty.write_all(prompt.as_bytes());
+    let mut answer = String::new();
+    let mut byte = [0u8; 1];
+    loop {
+        match tty.read(&mut byte) {
+            Ok(0) => break,
+            Ok(_) => {
+                if byte[0] == b'\n' {
+                    break;
+                }
+                answer.push(byte[0] as char);
+            }
+            Err(_) => break,
+        }
+    }
+    if answer.is_empty() {
+        None
+    } else {
+        Some(answer)
+    }
+}
+
+/// Count the commits reachable from `new` but not from `old` by walking the
+/// revision range with a [`git2::Revwalk`].
+fn count_new_commits(repo: &git2::Repository, old: git2::Oid, new: git2::Oid) -> usize {
+    let mut revwalk = match repo.revwalk() {
+        Ok(r) => r,
+        Err(_) => return 0,
+    };
+    if revwalk.push(new).is_err() {
+        return 0;
+    }
+    let _ = revwalk.hide(old);
+    revwalk.flatten().count()
+}
+
+fn command_hook(action: HookAction) -> Result<(), Error> {
+    let mut data = load_app_data()?;
+
+    let (resolv_path, requested_list, requested_remove) = match &action {
+        HookAction::Add { repo, .. } => (repo.clone(), false, None),
+        HookAction::List { repo } => (repo.clone(), true, None),
+        HookAction::Remove { repo, index } => (repo.clone(), false, Some(*index)),
+    };
+
+    let resolv_path = match resolv_path {
+        Some(p) => p,
+        None => std::env::current_dir().expect("should be able to access the cwd"),
+    };
+
+    let entry = data
+        .tracked_repos
+        .iter_mut()
+        .find(|r| same_file::is_same_file(&resolv_path, &r.path).unwrap_or(false));
+
+    let entry = match entry {
+        Some(e) => e,
+        None => {
+            println!(
+                "there were no tracked repositories at {}",
+                resolv_path.to_string_lossy()
+            );
+            return Ok(());
+        }
+    };
+
+    match action {
+        HookAction::Add { command, .. } => {
+            entry.on_update.push(command);
+        }
+        HookAction::Remove { .. } => {
+            let index = requested_remove.unwrap();
+            if index < entry.on_update.len() {
+                entry.on_update.remove(index);
+            } else {
+                println!("no hook with index {index}");
+            }
+        }
+        HookAction::List { .. } => {}
+    }
+
+    if requested_list {
+        for (i, hook) in entry.on_update.iter().enumerate() {
+            println!("{i:>2} | {}", hook.join(" "));
+        }
+    }
+
+    write_app_data(data)
+}
+
+fn command_clone(url: String, dest: Option<PathBuf>) -> Result<(), Error> {
+    let mut data = load_app_data()?;
+
+    // Default the destination to the repository name derived from the URL.
+    let dest = dest.unwrap_or_else(|| {
+        let name = url
+            .trim_end_matches('/')
+            .rsplit(['/', ':'])
+            .next()
+            .unwrap_or("repository")
+            .trim_end_matches(".git");
+        PathBuf::from(name)
+    });
+
+    // Skip the clone when the destination already holds a valid repository.
+    if dest.exists() {
+        match git2::Repository::open(&dest) {
+            Ok(_) => println!("{} already exists, skipping clone", dest.display()),
+            Err(_) => return Err(Error::NotAGitRepository(dest)),
+        }
+    } else {
+        let mut options = git2::FetchOptions::new();
+        options.remote_callbacks(make_remote_callbacks());
+        let mut builder = git2::build::RepoBuilder::new();
+        builder.fetch_options(options);
+        builder
+            .clone(&url, &dest)
+            .map_err(|_| Error::NotAGitRepository(dest.clone()))?;
+        println!("cloned {url} into {}", dest.display());
+    }
+
+    let path = dest
+        .canonicalize()
+        .map_err(|source| Error::Io { path: dest.clone(), source })?;
+
+    // Don't create a duplicate entry if this path is already tracked.
+    if data
+        .tracked_repos
+        .iter()
+        .any(|r| same_file::is_same_file(&path, &r.path).unwrap_or(false))
+    {
+        println!("{} is already tracked", path.display());
+        return Ok(());
+    }
+
+    data.tracked_repos.push(TrackedRepo {
+        path,
+        last_fetch: SystemTime::now(),
+        notify: None,
+        on_update: Vec::new(),
+    });
+
+    write_app_data(data)
+}
+
+/// Run each configured `on_update` command once per branch that advanced,
+/// exposing that branch's own range and commit count through environment
+/// variables so `GIT_SUBSCRIBE_OLD`/`GIT_SUBSCRIBE_NEW` always name the same
+/// ref.
+fn run_hooks(entry: &TrackedRepo, updates: &[BranchUpdate]) {
+    if entry.on_update.is_empty() {
+        return;
+    }
+
+    for update in updates {
+        for hook in &entry.on_update {
+            let (program, args) = match hook.split_first() {
+                Some(parts) => parts,
+                None => continue,
+            };
+            let status = std::process::Command::new(program)
+                .args(args)
+                .current_dir(&entry.path)
+                .env("GIT_SUBSCRIBE_BRANCH", &update.branch)
+                .env("GIT_SUBSCRIBE_OLD", update.old_tip.to_string())
+                .env("GIT_SUBSCRIBE_NEW", update.new_tip.to_string())
+                .env("GIT_SUBSCRIBE_COUNT", update.count.to_string())
+                .status();
+            if let Err(e) = status {
+                println!("{}: hook `{}` failed: {e}", entry.path.display(), hook.join(" "));
+            }
+        }
+    }
+}
+
+/// Render a one-line-per-commit summary (short hash, author, subject) by
+/// walking the revision range an update covers.
+fn summarize_commits(repo: &git2::Repository, update: &BranchUpdate) -> Vec<String> {
+    let mut revwalk = match repo.revwalk() {
+        Ok(r) => r,
+        Err(_) => return Vec::new(),
+    };
+    if revwalk.push(update.new_tip).is_err() {
+        return Vec::new();
+    }
+    let _ = revwalk.hide(update.old_tip);
+
+    let mut lines = Vec::new();
+    for oid in revwalk.flatten() {
+        let commit = match repo.find_commit(oid) {
+            Ok(c) => c,
+            Err(_) => continue,
+        };
+        let author = commit.author();
+        let name = author.name().unwrap_or("unknown");
+        let subject = commit.summary().unwrap_or("");
+        let short: String = oid.to_string().chars().take(8).collect();
+        lines.push(format!("{short} {name}: {subject}"));
+    }
+    lines
+}
+
+/// Send a summary email describing the commits a tracked repo just received.
+fn send_notification(entry: &TrackedRepo, updates: &[BranchUpdate]) {
+    let config = match &entry.notify {
+        Some(c) => c,
+        None => return,
+    };
+
+    let repo = match git2::Repository::open(&entry.path) {
+        Ok(r) => r,
+        Err(e) => {
+            println!("{}: unable to open for notification: {e}", entry.path.display());
+            return;
+        }
+    };
+
+    let total: usize = updates.iter().map(|u| u.count).sum();
+    let subject = format!("[git-subscribe] {} new commit(s) in {}", total, entry.path.display());
+
+    let mut body = String::new();
+    for update in updates {
+        body.push_str(&format!("{} (+{}):\n", update.branch, update.count));
+        for line in summarize_commits(&repo, update) {
+            body.push_str("  ");
+            body.push_str(&line);
+            body.push('\n');
+        }
+        body.push('\n');
+    }
+
+    if let Err(e) = deliver_mail(config, &subject, &body) {
+        println!("{}: failed to send notification: {e}", entry.path.display());
+    }
+}
+
+/// Deliver a message either through an SMTP relay or a sendmail-style command.
+fn deliver_mail(config: &NotifyConfig, subject: &str, body: &str) -> Result<(), String> {
+    use lettre::message::Message;
+
+    let email = Message::builder()
+        .from(config.from.parse().map_err(|e| format!("bad from address: {e}"))?)
+        .to(config.to.parse().map_err(|e| format!("bad to address: {e}"))?)
+        .subject(subject)
+        .body(body.to_owned())
+        .map_err(|e| format!("unable to build message: {e}"))?;
+
+    if let Some(relay) = &config.smtp {
+        use lettre::{SmtpTransport, Transport};
+        // The field is `host:port`; `builder_dangerous` wants a bare host, so
+        // split the port off and apply it explicitly (defaulting to 25).
+        let (host, port) = match relay.rsplit_once(':') {
+            Some((h, p)) => {
+                let port = p.parse().map_err(|e| format!("bad smtp port: {e}"))?;
+                (h, port)
+            }
+            None => (relay.as_str(), 25),
+        };
+        let mailer = SmtpTransport::builder_dangerous(host).port(port).build();
+        mailer.send(&email).map_err(|e| e.to_string())?;
+        return Ok(());
+    }
+
+    if let Some(argv) = &config.sendmail {
+        let (program, rest) = argv.split_first().ok_or("empty sendmail command")?;
+        let mut child = std::process::Command::new(program)
+            .args(rest)
+            .stdin(std::process::Stdio::piped())
+            .spawn()
+            .map_err(|e| e.to_string())?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(&email.formatted())
+                .map_err(|e| e.to_string())?;
+        }
+        child.wait().map_err(|e| e.to_string())?;
+        return Ok(());
+    }
+
+    Err("no smtp or sendmail delivery configured".to_owned())
+}
+
+/// Print a prompt and read a line of ordinary (echoed) input from stdin,
+/// returning `None` on EOF or an empty line.
+fn read_prompt(prompt: &str) -> Option<String> {
+    print!("{prompt}");
+    std::io::stdout().flush().ok()?;
+    let mut line = String::new();
+    match std::io::stdin().read_line(&mut line) {
+        Ok(0) => None,
+        Ok(_) => {
+            let trimmed = line.trim_end_matches(['\r', '\n']).to_owned();
+            if trimmed.is_empty() {
+                None
+            } else {
+                Some(trimmed)
+            }
+        }
+        Err(_) => None,
+    }
+}
+
+/// Score how well `query` fuzzy-matches `candidate`, case-insensitively.
+///
+/// Query characters are matched greedily left-to-right; each match awards a
+/// base point, with a bonus when it is consecutive with the previous match or
+/// lands on a word boundary (start of string, or right after a path
+/// separator, `-` or `_`). Returns `None` when not every query character can
+/// be matched, so non-matching candidates are rejected outright.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    const MATCH: i32 = 1;
+    const CONSECUTIVE_BONUS: i32 = 2;
+    const BOUNDARY_BONUS: i32 = 2;
+
+    let candidate: Vec<char> = candidate.chars().collect();
+    let mut score = 0;
+    let mut last_match: Option<usize> = None;
+    let mut cand_idx = 0;
+
+    for q in query.chars().filter(|c| !c.is_whitespace()) {
+        let q = q.to_ascii_lowercase();
+        let mut found = None;
+        while cand_idx < candidate.len() {
+            let c = candidate[cand_idx];
+            if c.to_ascii_lowercase() == q {
+                found = Some(cand_idx);
+                break;
+            }
+            cand_idx += 1;
+        }
+
+        let idx = found?;
+        score += MATCH;
+        if last_match == Some(idx.wrapping_sub(1)) {
+            score += CONSECUTIVE_BONUS;
+        }
+        let boundary = idx == 0
+            || matches!(candidate[idx - 1], '/' | '\\' | '-' | '_');
+        if boundary {
+            score += BOUNDARY_BONUS;
+        }
+        last_match = Some(idx);
+        cand_idx = idx + 1;
+    }
+
+    Some(score)
+}
+
+/// Present the tracked repositories in a fuzzy-filterable selector and return
+/// the index of the chosen one, or `None` if the user aborts.
+///
+/// Note: this is a type-a-query, redraw, then pick-by-number loop rather than
+/// per-keystroke live filtering — rendering truly live would need raw-terminal
+/// input, which the crate has no dependency for.
+fn pick_repo(data: &ApplicationData) -> Option<usize> {
+    if data.tracked_repos.is_empty() {
+        println!("no repositories are being tracked");
+        return None;
+    }
+
+    let paths: Vec<String> = data
+        .tracked_repos
+        .iter()
+        .map(|r| r.path.to_string_lossy().into_owned())
+        .collect();
+
+    loop {
+        // Rank the candidates against the current query and render every
+        // match, best score first, for the user to choose from.
+        let query = read_prompt("filter> ").unwrap_or_default();
+
+        let mut ranked: Vec<(i32, usize)> = paths
+            .iter()
+            .enumerate()
+            .filter_map(|(i, p)| fuzzy_score(&query, p).map(|s| (s, i)))
+            .collect();
+        ranked.sort_by(|a, b| b.0.cmp(&a.0));
+
+        if ranked.is_empty() {
+            println!("no matches; try again");
+            continue;
+        }
+
+        for (rank, (score, i)) in ranked.iter().enumerate() {
+            println!("{rank:>2} [{score:>2}] {}", paths[*i]);
+        }
+
+        let choice = read_prompt("select a number (blank to refine, q to quit): ");
+        match choice.as_deref() {
+            Some("q") => return None,
+            Some(s) if !s.is_empty() => {
+                if let Ok(rank) = s.trim().parse::<usize>() {
+                    if let Some((_, i)) = ranked.get(rank) {
+                        return Some(*i);
+                    }
+                }
+                println!("invalid selection");
+            }
+            _ => {}
+        }
+    }
+}
+
+fn load_app_data() -> Result<ApplicationData, Error> {
+    let path = data_file_path()?;
+
+    let file: Option<File> = match OpenOptions::new().read(true).write(false).open(&path) {
         Ok(f) => Some(f),
         Err(e) if e.kind() == ErrorKind::NotFound => None,
-        Err(e) if e.kind() == ErrorKind::PermissionDenied => {
-            panic!("unable to open data directory");
+        Err(source) => {
+            return Err(Error::Io {
+                path: path.clone(),
+                source,
+            })
         }
-        Err(_) => panic!("unexpected error opening {}", DATA_FILE_PATH.display()),
     };
 
-    match file {
+    let data = match file {
         Some(mut f) => {
             let mut buf: Vec<u8> = Vec::new();
-            let i = f.read_to_end(&mut buf);
-            if i.is_err() {
-                panic!("error reading app database {}", i.unwrap_err().to_string())
-            }
-            match toml::from_slice(buf.as_ref()) {
-                Ok(data) => data,
-                Err(e) => panic!("error reading app database {}", e.to_string()),
-            }
+            f.read_to_end(&mut buf).map_err(|source| Error::Io {
+                path: path.clone(),
+                source,
+            })?;
+            toml::from_slice(buf.as_ref()).map_err(|source| Error::Toml {
+                path: path.clone(),
+                source,
+            })?
         }
         None => ApplicationData {
             tracked_repos: Vec::new(),
         },
+    };
+
+    Ok(data)
+}
+
+/// Make sure every tracked path still exists and still opens as a git
+/// repository, failing early with an actionable error otherwise. Call this
+/// from commands that actually touch the repos (`fetch`/`watch`); the shared
+/// loader must stay lenient so `list`/`remove` can still clean up stale
+/// entries.
+fn validate_app_data(data: &ApplicationData) -> Result<(), Error> {
+    for entry in &data.tracked_repos {
+        git2::Repository::open(&entry.path)
+            .map_err(|_| Error::NotAGitRepository(entry.path.clone()))?;
     }
+    Ok(())
 }
 
-fn write_app_data(data: ApplicationData) {
-    let mut file: File = match OpenOptions::new()
+fn write_app_data(data: ApplicationData) -> Result<(), Error> {
+    let path = data_file_path()?;
+
+    let mut file: File = OpenOptions::new()
         .write(true)
         .create(true)
         .truncate(true)
-        .open(DATA_FILE_PATH.as_path())
-    {
-        Ok(f) => f,
-        Err(e) => panic!("unable to open file due to following error: {e}"),
-    };
-
-    let s = toml::to_string(&data).expect("serialization shouldn't fail ");
+        .open(&path)
+        .map_err(|source| Error::Io {
+            path: path.clone(),
+            source,
+        })?;
 
-    let x = file.write_all(s.as_bytes());
+    let s = toml::to_string(&data).expect("serialization shouldn't fail");
 
-    match x {
-        Ok(_) => {}
-        Err(e) => panic!("unexpected error writing to file {e}"),
-    }
+    file.write_all(s.as_bytes()).map_err(|source| Error::Io {
+        path: path.clone(),
+        source,
+    })
 }